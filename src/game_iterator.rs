@@ -18,6 +18,20 @@ pub struct RenderArgs {
     pub width: u32,
     /// The height of rendered area.
     pub height: u32,
+    /// The logical width the game should render at.
+    ///
+    /// Equal to `width` unless a fixed resolution is set in
+    /// `GameIteratorSettings`, in which case the game always draws at
+    /// this resolution and the loop scales/letterboxes it to fit the window.
+    pub draw_width: u32,
+    /// The logical height the game should render at. See `draw_width`.
+    pub draw_height: u32,
+    /// The integer scale factor applied to go from logical to window size.
+    pub scale: u32,
+    /// Horizontal letterbox/pillarbox offset, in window pixels.
+    pub offset_x: u32,
+    /// Vertical letterbox/pillarbox offset, in window pixels.
+    pub offset_y: u32,
 }
 
 /// Update argument.
@@ -82,6 +96,60 @@ pub struct MouseScrollArgs {
     pub y: f64,
 }
 
+/// Controller button press arguments.
+#[deriving(Clone)]
+pub struct ControllerButtonPressArgs {
+    /// Which controller fired the event.
+    pub id: u32,
+    /// Controller button.
+    pub button: u32,
+}
+
+/// Controller button release arguments.
+#[deriving(Clone)]
+pub struct ControllerButtonReleaseArgs {
+    /// Which controller fired the event.
+    pub id: u32,
+    /// Controller button.
+    pub button: u32,
+}
+
+/// Window resize arguments.
+#[deriving(Clone)]
+pub struct ResizeArgs {
+    /// The new width of the window.
+    pub width: u32,
+    /// The new height of the window.
+    pub height: u32,
+}
+
+/// Window focus arguments.
+#[deriving(Clone)]
+pub struct FocusArgs {
+    /// Whether the window gained (`true`) or lost (`false`) focus.
+    pub focused: bool,
+}
+
+/// Text input arguments.
+#[deriving(Clone)]
+pub struct TextInputArgs {
+    /// The translated text, e.g. respecting layout, shift/alt-gr and IME composition.
+    pub text: String,
+}
+
+/// Controller axis arguments, reported after deadzone processing.
+#[deriving(Clone)]
+pub struct ControllerAxisArgs {
+    /// Which controller fired the event.
+    pub id: u32,
+    /// Controller axis.
+    pub axis: u32,
+    /// Axis value in `[-1.0, 1.0]`.
+    pub x: f64,
+    /// Second axis value for stick axes, `0.0` for 1-D axes such as triggers.
+    pub y: f64,
+}
+
 /// Contains the different game events.
 #[deriving(Clone)]
 pub enum GameEvent {
@@ -102,7 +170,27 @@ pub enum GameEvent {
     /// Moved mouse relative, not bounded by cursor.
     MouseRelativeMove(MouseRelativeMoveArgs),
     /// Scrolled mouse.
-    MouseScroll(MouseScrollArgs)
+    MouseScroll(MouseScrollArgs),
+    /// Pressed a controller button.
+    ControllerButtonPress(ControllerButtonPressArgs),
+    /// Released a controller button.
+    ControllerButtonRelease(ControllerButtonReleaseArgs),
+    /// Moved a controller axis, after deadzone processing.
+    ControllerAxis(ControllerAxisArgs),
+    /// Translated text produced by a keystroke, for text entry.
+    TextInput(TextInputArgs),
+    /// The window was resized.
+    Resize(ResizeArgs),
+    /// The window gained or lost focus.
+    Focus(FocusArgs),
+    /// The window manager asked the window to close.
+    ///
+    /// Unless `confirm_close` was called first, the loop delivers this before
+    /// `should_close()` ends the iterator, giving the game a chance to veto or
+    /// defer shutdown (via `cancel_close`), e.g. to prompt "save before quit?".
+    /// The iterator auto-acknowledges once this has been delivered, so games
+    /// that ignore it still close promptly, same as before this event existed.
+    CloseRequested
 }
 
 #[deriving(Show)]
@@ -122,6 +210,76 @@ pub struct GameIteratorSettings {
     pub updates_per_second: u64,
     /// The maximum number of frames per second (FPS target).
     pub max_frames_per_second: u64,
+    /// Deadzone applied to controller axes, in `[0.0, 1.0]`.
+    pub controller_deadzone: f64,
+    /// Pins rendering to a fixed logical resolution (width, height),
+    /// letterboxed and integer-scaled to fit the actual window size.
+    pub fixed_resolution: Option<(u32, u32)>,
+    /// The maximum number of `Update` events fired between two `Render`
+    /// events, protecting against a spiral of death after a stall.
+    pub max_updates_per_frame: u64,
+    /// The maximum extrapolated time, in seconds, passed to `Render` as
+    /// `ext_dt`, regardless of how long the frame actually stalled for.
+    pub max_ext_dt: f64,
+}
+
+/// Computes the largest integer scale factor that fits `(logical_w, logical_h)`
+/// inside `(win_w, win_h)`, and the offsets needed to center it.
+fn fit_fixed_resolution(
+    win_w: u32, win_h: u32,
+    logical_w: u32, logical_h: u32
+) -> (u32, u32, u32) {
+    // Guard against a fat-fingered `GameIteratorSettings.fixed_resolution`
+    // of 0 in either dimension, which would otherwise divide by zero below.
+    let logical_w = cmp::max(1, logical_w);
+    let logical_h = cmp::max(1, logical_h);
+    let scale = cmp::max(1, cmp::min(win_w / logical_w, win_h / logical_h));
+    // The window can be smaller than the pinned resolution (e.g. shrunk
+    // below it), in which case `logical_* * scale` exceeds `win_*` and the
+    // naive subtraction would underflow. Saturate to 0 and let the draw
+    // area get cropped against the window instead.
+    let offset_x = win_w.saturating_sub(logical_w * scale) / 2;
+    let offset_y = win_h.saturating_sub(logical_h * scale) / 2;
+    (scale, offset_x, offset_y)
+}
+
+/// Applies a radial deadzone to a two-axis stick, rescaling so motion
+/// starts smoothly at the deadzone edge instead of snapping.
+fn deadzone_stick(x: f64, y: f64, deadzone: f64) -> (f64, f64) {
+    let m = (x * x + y * y).sqrt();
+    if m < deadzone || m == 0.0 {
+        (0.0, 0.0)
+    } else {
+        let scale = (m - deadzone) / (1.0 - deadzone);
+        // Diagonal input on a square gate can exceed a magnitude of 1.0;
+        // cap it so each component stays within `[-1.0, 1.0]`.
+        let scale = if scale > 1.0 { 1.0 } else { scale };
+        (x / m * scale, y / m * scale)
+    }
+}
+
+/// Applies a simple 1-D deadzone clamp to a single axis such as a trigger.
+fn deadzone_axis(value: f64, deadzone: f64) -> f64 {
+    if value.abs() < deadzone {
+        0.0
+    } else {
+        value
+    }
+}
+
+/// Queryable time state tracked by the game loop.
+#[deriving(Clone)]
+pub struct Time {
+    /// Total seconds elapsed since the iterator was created.
+    pub total_seconds: f64,
+    /// Total number of `Update` events fired so far.
+    pub update_count: u64,
+    /// Total number of `Render` events fired so far.
+    pub frame_count: u64,
+    /// Delta time of the last update, in seconds.
+    pub dt: f64,
+    /// Smoothed frames-per-second estimate.
+    pub fps: f64,
 }
 
 /// A game loop iterator.
@@ -132,6 +290,10 @@ pub struct GameIteratorSettings {
 /// let game_iter_settings = GameIteratorSettings {
 ///     updates_per_second: 120,
 ///     max_frames_per_second: 60,
+///     controller_deadzone: 0.2,
+///     fixed_resolution: None,
+///     max_updates_per_frame: 10,
+///     max_ext_dt: 0.25,
 /// };
 /// let ref mut gl = Gl::new();
 /// for e in GameIterator::new(&mut window, &game_iter_settings) {
@@ -155,6 +317,16 @@ pub struct GameIterator<'a, W> {
     dt_update_in_ns: u64,
     dt_frame_in_ns: u64,
     dt: f64,
+    controller_deadzone: f64,
+    fixed_resolution: Option<(u32, u32)>,
+    start_time: u64,
+    update_count: u64,
+    frame_count: u64,
+    fps: f64,
+    updates_since_render: u64,
+    max_updates_per_frame: u64,
+    max_ext_dt: f64,
+    close_confirmed: bool,
 }
 
 static billion: u64 = 1_000_000_000;
@@ -177,6 +349,55 @@ impl<'a, W: GameWindow> GameIterator<'a, W> {
             dt_update_in_ns: billion / updates_per_second,
             dt_frame_in_ns: billion / max_frames_per_second,
             dt: 1.0 / updates_per_second as f64,
+            controller_deadzone: settings.controller_deadzone,
+            fixed_resolution: settings.fixed_resolution,
+            start_time: start,
+            update_count: 0,
+            frame_count: 0,
+            fps: 0.0,
+            updates_since_render: 0,
+            max_updates_per_frame: settings.max_updates_per_frame,
+            max_ext_dt: settings.max_ext_dt,
+            close_confirmed: false,
+        }
+    }
+
+    /// Triggers rumble/haptic feedback on a controller. Rumble support is
+    /// optional: returns `false` if the backend or the controller doesn't
+    /// support it, so the game can fall back gracefully instead of assuming
+    /// the effect played.
+    pub fn set_rumble(&mut self, id: u32, strength: f64) -> bool {
+        self.game_window.set_rumble(id, strength)
+    }
+
+    /// Acknowledges a pending close, letting the iterator terminate on the
+    /// next `should_close()` check without waiting for `CloseRequested` to
+    /// be delivered first.
+    ///
+    /// This is rarely needed: the iterator already auto-acknowledges as
+    /// soon as `CloseRequested` has been yielded once, so unmigrated games
+    /// that never call this still close promptly on the OS close button or
+    /// Alt+F4, exactly like before this event existed. Call this only to
+    /// skip straight to closing without waiting out that one extra cycle.
+    pub fn confirm_close(&mut self) {
+        self.close_confirmed = true;
+    }
+
+    /// Withdraws the acknowledgement made when `CloseRequested` was
+    /// delivered, so the iterator keeps running while `should_close()` is
+    /// true until `confirm_close` is called.
+    pub fn cancel_close(&mut self) {
+        self.close_confirmed = false;
+    }
+
+    /// Returns a snapshot of the current time state.
+    pub fn time(&self) -> Time {
+        Time {
+            total_seconds: (time::precise_time_ns() - self.start_time) as f64 / billion as f64,
+            update_count: self.update_count,
+            frame_count: self.frame_count,
+            dt: self.dt,
+            fps: self.fps,
         }
     }
 }
@@ -189,20 +410,45 @@ for GameIterator<'a, W> {
         loop {
             match self.state {
                 RenderState => {
-                    if self.game_window.should_close() { return None; }
+                    if self.game_window.should_close() && self.close_confirmed {
+                        return None;
+                    }
 
                     let start_render = time::precise_time_ns();
+                    let measured_dt = (start_render - self.last_frame) as f64 / billion as f64;
+                    if measured_dt > 0.0 {
+                        self.fps = self.fps * 0.9 + (1.0 / measured_dt) * 0.1;
+                    }
                     self.last_frame = start_render;
 
                     let (w, h) = self.game_window.get_size();
                     if w != 0 && h != 0 {
+                        let (draw_w, draw_h, scale, offset_x, offset_y) =
+                            match self.fixed_resolution {
+                                Some((logical_w, logical_h)) => {
+                                    let (scale, offset_x, offset_y) =
+                                        fit_fixed_resolution(w, h, logical_w, logical_h);
+                                    (logical_w, logical_h, scale, offset_x, offset_y)
+                                },
+                                None => (w, h, 1, 0, 0),
+                            };
+
                         // Swap buffers next time.
                         self.state = SwapBuffersState;
+                        self.frame_count += 1;
+                        self.updates_since_render = 0;
+                        let ext_dt = (start_render - self.last_update) as f64 / billion as f64;
+                        let ext_dt = if ext_dt > self.max_ext_dt { self.max_ext_dt } else { ext_dt };
                         return Some(Render(RenderArgs {
                                 // Extrapolate time forward to allow smooth motion.
-                                ext_dt: (start_render - self.last_update) as f64 / billion as f64,
+                                ext_dt: ext_dt,
                                 width: w,
                                 height: h,
+                                draw_width: draw_w,
+                                draw_height: draw_h,
+                                scale: scale,
+                                offset_x: offset_x,
+                                offset_y: offset_y,
                             }
                         ));
                     }
@@ -215,6 +461,14 @@ for GameIterator<'a, W> {
                 },
                 UpdateLoopState => {
                     let current_time = time::precise_time_ns();
+                    if self.updates_since_render >= self.max_updates_per_frame {
+                        // We've fallen behind too far to catch up (GC pause,
+                        // window drag, breakpoint, ...). Drop the backlog
+                        // instead of bursting updates forever.
+                        self.last_update = current_time;
+                        self.state = RenderState;
+                        continue;
+                    }
                     let next_frame = self.last_frame + self.dt_frame_in_ns;
                     let next_update = self.last_update + self.dt_update_in_ns;
                     let next_event = cmp::min(next_frame, next_update);
@@ -266,6 +520,62 @@ for GameIterator<'a, W> {
                                 y: y
                             }))
                         },
+                        event::ControllerButtonPressed(id, button) => {
+                            Some(ControllerButtonPress(ControllerButtonPressArgs {
+                                id: id,
+                                button: button,
+                            }))
+                        },
+                        event::ControllerButtonReleased(id, button) => {
+                            Some(ControllerButtonRelease(ControllerButtonReleaseArgs {
+                                id: id,
+                                button: button,
+                            }))
+                        },
+                        event::ControllerStickMoved(id, axis, x, y) => {
+                            let (x, y) = deadzone_stick(x, y, self.controller_deadzone);
+                            Some(ControllerAxis(ControllerAxisArgs {
+                                id: id,
+                                axis: axis,
+                                x: x,
+                                y: y,
+                            }))
+                        },
+                        event::ControllerTriggerMoved(id, axis, value) => {
+                            Some(ControllerAxis(ControllerAxisArgs {
+                                id: id,
+                                axis: axis,
+                                x: deadzone_axis(value, self.controller_deadzone),
+                                y: 0.0,
+                            }))
+                        },
+                        event::TextComposed(text) => {
+                            Some(TextInput(TextInputArgs {
+                                text: text,
+                            }))
+                        },
+                        event::Resized(w, h) => {
+                            Some(Resize(ResizeArgs {
+                                width: w,
+                                height: h,
+                            }))
+                        },
+                        event::FocusChanged(focused) => {
+                            Some(Focus(FocusArgs {
+                                focused: focused,
+                            }))
+                        },
+                        event::CloseRequested => {
+                            // Auto-acknowledge on delivery so unmigrated
+                            // games (that never call `confirm_close`) still
+                            // close promptly instead of hanging forever;
+                            // a game that wants to veto/defer shutdown can
+                            // still act on this event, e.g. by calling
+                            // `cancel_close` before its next call into the
+                            // iterator.
+                            self.close_confirmed = true;
+                            Some(CloseRequested)
+                        },
                         event::NoEvent => {
                             self.state = UpdateState;
                             // Explicitly continue because otherwise the result
@@ -284,6 +594,8 @@ for GameIterator<'a, W> {
                 UpdateState => {
                     self.state = UpdateLoopState;
                     self.last_update += self.dt_update_in_ns;
+                    self.update_count += 1;
+                    self.updates_since_render += 1;
                     return Some(Update(UpdateArgs{
                         dt: self.dt,
                     }));